@@ -0,0 +1,96 @@
+use crate::config::ReminderConfig;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Utc};
+
+/// Length of each study block on the exported calendar.
+const STUDY_BLOCK_MINUTES: i64 = 30;
+
+const ICS_WEEKDAYS: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+fn ics_datetime(dt: NaiveDateTime) -> String {
+    dt.format("%Y%m%dT%H%M%S").to_string()
+}
+
+/// Builds an iCalendar document with a weekly recurring study block for each
+/// reminder day, starting from the first matching day on or after `today`.
+/// Study blocks use floating local time; `now` is only used for DTSTAMP,
+/// which must be in UTC.
+pub fn build_study_plan_ics(
+    reminders: &ReminderConfig,
+    today: NaiveDate,
+    now: DateTime<Utc>,
+) -> Result<String, String> {
+    if !reminders.enabled || reminders.days.is_empty() {
+        return Err("Study reminders are not enabled".to_string());
+    }
+    let time = crate::reminders::parse_time(&reminders.time)?;
+
+    let mut days: Vec<u32> = reminders
+        .days
+        .iter()
+        .copied()
+        .filter(|d| (1..=7).contains(d))
+        .collect();
+    days.sort_unstable();
+    days.dedup();
+    if days.is_empty() {
+        return Err("No valid reminder days configured".to_string());
+    }
+
+    let first_day = (0..7)
+        .map(|offset| today + Duration::days(offset))
+        .find(|date| days.contains(&date.weekday().number_from_monday()))
+        .unwrap_or(today);
+    let start = first_day.and_time(time);
+    let end = start + Duration::minutes(STUDY_BLOCK_MINUTES);
+    let by_day: Vec<&str> = days.iter().map(|d| ICS_WEEKDAYS[*d as usize - 1]).collect();
+
+    let lines = [
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//English In Use//Study Plan//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "BEGIN:VEVENT".to_string(),
+        "UID:study-reminder@english-in-use".to_string(),
+        format!("DTSTAMP:{}", now.format("%Y%m%dT%H%M%SZ")),
+        format!("DTSTART:{}", ics_datetime(start)),
+        format!("DTEND:{}", ics_datetime(end)),
+        format!("RRULE:FREQ=WEEKLY;BYDAY={}", by_day.join(",")),
+        "SUMMARY:English study".to_string(),
+        "END:VEVENT".to_string(),
+        "END:VCALENDAR".to_string(),
+    ];
+
+    Ok(lines.join("\r\n") + "\r\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_study_plan_ics() {
+        let reminders = ReminderConfig {
+            enabled: true,
+            time: "19:30".to_string(),
+            days: vec![5, 1, 3, 3],
+        };
+        // 2026-10-13 is a Tuesday, so the first block is on Wednesday
+        let today = NaiveDate::from_ymd_opt(2026, 10, 13).unwrap();
+        let now = today.and_hms_opt(9, 0, 0).unwrap().and_utc();
+
+        let ics = build_study_plan_ics(&reminders, today, now).unwrap();
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTAMP:20261013T090000Z\r\n"));
+        assert!(ics.contains("DTSTART:20261014T193000\r\n"));
+        assert!(ics.contains("DTEND:20261014T200000\r\n"));
+        assert!(ics.contains("RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn test_build_study_plan_ics_requires_enabled_reminders() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 13).unwrap();
+        let now = today.and_hms_opt(9, 0, 0).unwrap().and_utc();
+        assert!(build_study_plan_ics(&ReminderConfig::default(), today, now).is_err());
+    }
+}
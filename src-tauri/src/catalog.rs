@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+/// Object key of the optional catalog manifest at the book source root.
+pub const CATALOG_KEY: &str = "catalog.json";
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct CatalogBook {
+    pub code: String,
+    pub title: String,
+    #[serde(default)]
+    pub group: Option<String>,
+    /// Object key of the cover image, relative to the source root.
+    #[serde(default)]
+    pub cover: Option<String>,
+    /// Total content size in bytes.
+    #[serde(default)]
+    pub size: Option<u64>,
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct Catalog {
+    #[serde(default)]
+    pub books: Vec<CatalogBook>,
+}
+
+impl Catalog {
+    pub fn parse(data: &[u8]) -> Result<Self, String> {
+        serde_json::from_slice(data).map_err(|e| format!("Invalid {}: {}", CATALOG_KEY, e))
+    }
+
+    pub fn find(&self, code: &str) -> Option<&CatalogBook> {
+        self.books.iter().find(|book| book.code == code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_catalog() {
+        let data = br#"{
+            "books": [
+                {
+                    "code": "egu-int",
+                    "title": "English Grammar in Use",
+                    "group": "grammar",
+                    "cover": "books/egu-int/cover.jpg",
+                    "size": 734003200,
+                    "version": "2024.1"
+                },
+                { "code": "evu-adv", "title": "English Vocabulary in Use" }
+            ]
+        }"#;
+
+        let catalog = Catalog::parse(data).expect("Failed to parse catalog");
+        assert_eq!(catalog.books.len(), 2);
+        assert_eq!(catalog.find("egu-int").unwrap().size, Some(734003200));
+        let minimal = catalog.find("evu-adv").unwrap();
+        assert_eq!(minimal.group, None);
+        assert_eq!(minimal.version, None);
+        assert!(catalog.find("missing").is_none());
+    }
+
+    #[test]
+    fn test_parse_invalid_catalog() {
+        assert!(Catalog::parse(br#"{ "books": [{ "title": "No code" }] }"#).is_err());
+    }
+}
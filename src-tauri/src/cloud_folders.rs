@@ -0,0 +1,245 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How often hydration checks whether a placeholder has been downloaded.
+const HYDRATION_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Serialize, PartialEq, Clone, Copy)]
+pub enum CloudProvider {
+    ICloudDrive,
+    OneDrive,
+}
+
+impl CloudProvider {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CloudProvider::ICloudDrive => "iCloud Drive",
+            CloudProvider::OneDrive => "OneDrive",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq, Default)]
+pub struct SyncState {
+    pub provider: Option<CloudProvider>,
+    pub total_files: usize,
+    /// Files that exist only as cloud placeholders and will be downloaded on first read.
+    pub placeholder_files: usize,
+    /// Entries that couldn't be read (e.g. subfolders without permission) and
+    /// were left out of the counts.
+    pub skipped_entries: usize,
+}
+
+/// Roots of the OneDrive folders announced by the OneDrive client on Windows.
+fn onedrive_env_roots() -> Vec<PathBuf> {
+    ["OneDrive", "OneDriveConsumer", "OneDriveCommercial"]
+        .iter()
+        .filter_map(std::env::var_os)
+        .map(PathBuf::from)
+        .collect()
+}
+
+pub fn detect_provider_in(
+    path: &Path,
+    home: Option<&Path>,
+    onedrive_roots: &[PathBuf],
+) -> Option<CloudProvider> {
+    if onedrive_roots.iter().any(|root| path.starts_with(root)) {
+        return Some(CloudProvider::OneDrive);
+    }
+
+    let home = home?;
+    if path.starts_with(home.join("Library/Mobile Documents")) {
+        return Some(CloudProvider::ICloudDrive);
+    }
+    // macOS File Provider locations, e.g. ~/Library/CloudStorage/OneDrive-Personal
+    let relative = path.strip_prefix(home.join("Library/CloudStorage")).ok()?;
+    let folder = relative.components().next()?.as_os_str().to_string_lossy();
+    if folder.starts_with("OneDrive") {
+        Some(CloudProvider::OneDrive)
+    } else if folder.starts_with("iCloud") {
+        Some(CloudProvider::ICloudDrive)
+    } else {
+        None
+    }
+}
+
+/// Detects whether `path` lives inside an iCloud Drive or OneDrive folder.
+pub fn detect_provider(path: &Path) -> Option<CloudProvider> {
+    detect_provider_in(
+        path,
+        crate::paths::home_dir().as_deref(),
+        &onedrive_env_roots(),
+    )
+}
+
+/// The stub iCloud leaves in place of a file that hasn't been downloaded:
+/// `dir/name.ext` becomes `dir/.name.ext.icloud`.
+fn icloud_stub_path(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_string_lossy();
+    Some(path.with_file_name(format!(".{}.icloud", name)))
+}
+
+fn is_icloud_stub_name(name: &str) -> bool {
+    name.starts_with('.') && name.ends_with(".icloud") && name.len() > ".icloud".len() + 1
+}
+
+#[cfg(windows)]
+fn has_placeholder_attributes(path: &Path) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_OFFLINE: u32 = 0x0000_1000;
+    const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x0004_0000;
+    const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x0040_0000;
+    fs::metadata(path)
+        .map(|m| {
+            m.file_attributes()
+                & (FILE_ATTRIBUTE_OFFLINE
+                    | FILE_ATTRIBUTE_RECALL_ON_OPEN
+                    | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS)
+                != 0
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(not(windows))]
+fn has_placeholder_attributes(_path: &Path) -> bool {
+    false
+}
+
+/// Whether `path` refers to a file whose contents are still in the cloud.
+pub fn is_placeholder(path: &Path) -> bool {
+    if path.exists() {
+        return has_placeholder_attributes(path);
+    }
+    icloud_stub_path(path).is_some_and(|stub| stub.exists())
+}
+
+/// Asks the sync client to download a placeholder and waits up to `timeout`.
+/// OneDrive hydrates files on first read, so only iCloud stubs need a request.
+pub async fn hydrate(path: &Path, timeout: Duration) -> Result<(), String> {
+    if path.exists() {
+        return Ok(());
+    }
+
+    if cfg!(target_os = "macos") {
+        let status = tokio::process::Command::new("brctl")
+            .arg("download")
+            .arg(path)
+            .status()
+            .await
+            .map_err(|e| format!("Failed to request iCloud download: {}", e))?;
+        if !status.success() {
+            return Err(format!(
+                "Failed to request iCloud download of {}: brctl exited with {}",
+                path.display(),
+                status
+            ));
+        }
+    }
+
+    let started = Instant::now();
+    while !path.exists() {
+        if started.elapsed() >= timeout {
+            return Err(format!(
+                "Timed out downloading {} from the cloud",
+                path.display()
+            ));
+        }
+        tokio::time::sleep(HYDRATION_POLL_INTERVAL).await;
+    }
+    Ok(())
+}
+
+fn count_files(dir: &Path, state: &mut SyncState) -> Result<(), String> {
+    for entry in fs::read_dir(dir).map_err(|e| e.to_string())? {
+        // One unreadable entry shouldn't fail the scan of the whole folder
+        let Some((entry, file_type)) = entry
+            .ok()
+            .and_then(|entry| entry.file_type().ok().map(|t| (entry, t)))
+        else {
+            state.skipped_entries += 1;
+            continue;
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+            if count_files(&path, state).is_err() {
+                state.skipped_entries += 1;
+            }
+        } else if is_icloud_stub_name(&entry.file_name().to_string_lossy()) {
+            state.total_files += 1;
+            state.placeholder_files += 1;
+        } else {
+            state.total_files += 1;
+            if has_placeholder_attributes(&path) {
+                state.placeholder_files += 1;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reports the cloud provider of a local folder and how many of its files
+/// are still placeholders.
+pub fn scan_sync_state(root: &Path) -> Result<SyncState, String> {
+    let mut state = SyncState {
+        provider: detect_provider(root),
+        ..SyncState::default()
+    };
+    count_files(root, &mut state)?;
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_provider_in() {
+        let home = Path::new("/Users/me");
+        let onedrive = vec![PathBuf::from("/mnt/c/Users/me/OneDrive")];
+
+        assert_eq!(
+            detect_provider_in(
+                Path::new("/Users/me/Library/Mobile Documents/com~apple~CloudDocs/Books"),
+                Some(home),
+                &[]
+            ),
+            Some(CloudProvider::ICloudDrive)
+        );
+        assert_eq!(
+            detect_provider_in(
+                Path::new("/Users/me/Library/CloudStorage/OneDrive-Personal/Books"),
+                Some(home),
+                &[]
+            ),
+            Some(CloudProvider::OneDrive)
+        );
+        assert_eq!(
+            detect_provider_in(Path::new("/mnt/c/Users/me/OneDrive/Books"), None, &onedrive),
+            Some(CloudProvider::OneDrive)
+        );
+        assert_eq!(
+            detect_provider_in(Path::new("/Users/me/Books"), Some(home), &onedrive),
+            None
+        );
+    }
+
+    #[test]
+    fn test_icloud_placeholders() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("book")).unwrap();
+        fs::write(dir.path().join("book/page1.jpg"), "page").unwrap();
+        fs::write(dir.path().join("book/.page2.jpg.icloud"), "stub").unwrap();
+
+        assert!(!is_placeholder(&dir.path().join("book/page1.jpg")));
+        assert!(is_placeholder(&dir.path().join("book/page2.jpg")));
+        assert!(!is_placeholder(&dir.path().join("book/page3.jpg")));
+
+        let state = scan_sync_state(dir.path()).unwrap();
+        assert_eq!(state.total_files, 2);
+        assert_eq!(state.placeholder_files, 1);
+    }
+}
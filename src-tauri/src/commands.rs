@@ -1,117 +1,453 @@
-use crate::config::{AppConfig, BookSource, DatabaseConnection};
+use crate::catalog::{Catalog, CATALOG_KEY};
+use crate::cloud_folders::SyncState;
+use crate::config::{AppConfig, BookSource, DatabaseConnection, ReminderConfig};
+use crate::crash::CrashReport;
+use crate::migrate::MigrationReport;
+use crate::progress::{TaskKind, TaskReporter};
+use crate::r2::{ObjectMetadata, R2Permissions};
+use crate::recordings::Recording;
+use crate::reminders::ReminderState;
+use crate::sources::{ResolvedObject, SourceHealth};
+use chrono::{Local, Utc};
 use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, State, Window};
 
-fn get_config_path(app: &AppHandle) -> PathBuf {
-    // In a real app, you might want to handle errors better than unwrap
-    // but for now, we assume the app config dir is always available.
-    app.path().app_config_dir().expect("Could not resolve app config dir").join("config.toml")
+pub(crate) fn get_config_path(app: &AppHandle) -> PathBuf {
+    crate::paths::app_config_dir(app).join(crate::migrate::CONFIG_FILE)
+}
+
+pub(crate) fn get_crash_reports_dir(app: &AppHandle) -> PathBuf {
+    crate::paths::app_data_dir(app).join(crate::migrate::CRASH_REPORTS_DIR)
+}
+
+fn get_recordings_dir(app: &AppHandle) -> PathBuf {
+    crate::paths::app_data_dir(app).join(crate::migrate::RECORDINGS_DIR)
+}
+
+/// The saved config, falling back to defaults for connection settings when
+/// it can't be read.
+fn load_saved_config(app: &AppHandle) -> AppConfig {
+    AppConfig::load_from_path(&get_config_path(app)).unwrap_or_default()
 }
 
 #[tauri::command]
 pub fn load_config(app: AppHandle) -> Result<AppConfig, String> {
-    let path = get_config_path(&app);
-    AppConfig::load_from_path(&path)
+    crate::guard::catch_panic(|| {
+        let path = get_config_path(&app);
+        AppConfig::load_from_path(&path)
+    })
 }
 
 #[tauri::command]
 pub fn save_config(app: AppHandle, config: AppConfig) -> Result<(), String> {
-    let path = get_config_path(&app);
-    config.save_to_path(&path)
+    crate::guard::catch_panic(|| {
+        let path = get_config_path(&app);
+        config.save_to_path(&path)
+    })
 }
 
 #[tauri::command]
 pub fn export_config(path: String, config: AppConfig) -> Result<(), String> {
-    let path = PathBuf::from(path);
-    config.save_to_path(&path)
+    crate::guard::catch_panic(|| {
+        let path = PathBuf::from(path);
+        config.save_to_path(&path)
+    })
 }
 
 #[tauri::command]
 pub fn import_config(path: String) -> Result<AppConfig, String> {
-    let path = PathBuf::from(path);
-    AppConfig::load_from_path(&path)
+    crate::guard::catch_panic(|| {
+        let path = PathBuf::from(path);
+        AppConfig::load_from_path(&path)
+    })
 }
 
 #[tauri::command]
-pub async fn test_r2_connection(source: BookSource) -> Result<String, String> {
-    match &source {
-        BookSource::CloudflareR2 { bucket_name, .. } => {
-            let client = crate::r2::create_r2_client(&source).await?;
-            // Try to list 1 object to verify connection
-            client
-                .list_objects_v2()
-                .bucket(bucket_name)
-                .max_keys(1)
-                .send()
-                .await
-                .map_err(|e| format!("R2 connection failed: {}", e))?;
-            
-            Ok("Connection successful".to_string())
+pub async fn test_r2_connection(
+    app: AppHandle,
+    source: BookSource,
+) -> Result<R2Permissions, String> {
+    crate::guard::catch_panic_async(async move {
+        match &source {
+            BookSource::CloudflareR2 {
+                bucket_name,
+                key_prefix,
+                ..
+            } => {
+                let config = load_saved_config(&app);
+                let client = crate::r2::create_r2_client(
+                    &source,
+                    config.timeouts.r2(),
+                    &config.fault_injection,
+                )
+                .await?;
+                crate::r2::check_connection(&client, bucket_name, key_prefix.as_deref()).await
+            }
+            _ => Err("Invalid config type for R2 test".to_string()),
         }
-        _ => Err("Invalid config type for R2 test".to_string()),
-    }
+    })
+    .await
 }
 
 #[tauri::command]
-pub async fn list_r2_objects(source: BookSource) -> Result<Vec<String>, String> {
-    match &source {
-        BookSource::CloudflareR2 { bucket_name, .. } => {
-            let client = crate::r2::create_r2_client(&source).await?;
-            crate::r2::list_objects(&client, bucket_name).await
+pub async fn list_r2_objects(app: AppHandle, source: BookSource) -> Result<Vec<String>, String> {
+    crate::guard::catch_panic_async(async move {
+        match &source {
+            BookSource::CloudflareR2 {
+                bucket_name,
+                key_prefix,
+                ..
+            } => {
+                let config = load_saved_config(&app);
+                let client = crate::r2::create_r2_client(
+                    &source,
+                    config.timeouts.r2(),
+                    &config.fault_injection,
+                )
+                .await?;
+                crate::r2::list_objects(&client, bucket_name, key_prefix.as_deref()).await
+            }
+            _ => Err("Invalid config type for R2 list".to_string()),
         }
-        _ => Err("Invalid config type for R2 list".to_string()),
-    }
+    })
+    .await
 }
 
 #[tauri::command]
-pub async fn read_r2_object(source: BookSource, key: String) -> Result<Vec<u8>, String> {
-    match &source {
-        BookSource::CloudflareR2 { bucket_name, .. } => {
-            let client = crate::r2::create_r2_client(&source).await?;
-            crate::r2::get_object(&client, bucket_name, &key).await
+pub async fn read_r2_object(
+    app: AppHandle,
+    source: BookSource,
+    key: String,
+) -> Result<Vec<u8>, String> {
+    crate::guard::catch_panic_async(async move {
+        match &source {
+            BookSource::CloudflareR2 {
+                bucket_name,
+                key_prefix,
+                ..
+            } => {
+                let config = load_saved_config(&app);
+                let client = crate::r2::create_r2_client(
+                    &source,
+                    config.timeouts.r2(),
+                    &config.fault_injection,
+                )
+                .await?;
+                crate::r2::get_object(
+                    &client,
+                    bucket_name,
+                    &crate::r2::prefixed_key(key_prefix.as_deref(), &key),
+                )
+                .await
+            }
+            _ => Err("Invalid config type for R2 read".to_string()),
         }
-        _ => Err("Invalid config type for R2 read".to_string()),
-    }
-}
-
-#[tauri::command]
-pub async fn test_postgresql_connection(connection: DatabaseConnection) -> Result<String, String> {
-    match connection {
-        DatabaseConnection::PostgreSQL {
-            host,
-            port,
-            user,
-            password,
-            database,
-            ssl,
-        } => {
-            let mut config = postgres::Config::new();
-            config
-                .host(&host)
-                .port(port)
-                .user(&user)
-                .dbname(&database);
-
-            if let Some(pwd) = password {
-                config.password(&pwd);
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn head_r2_object(
+    app: AppHandle,
+    source: BookSource,
+    key: String,
+) -> Result<ObjectMetadata, String> {
+    crate::guard::catch_panic_async(async move {
+        match &source {
+            BookSource::CloudflareR2 {
+                bucket_name,
+                key_prefix,
+                ..
+            } => {
+                let config = load_saved_config(&app);
+                let client = crate::r2::create_r2_client(
+                    &source,
+                    config.timeouts.r2(),
+                    &config.fault_injection,
+                )
+                .await?;
+                crate::r2::head_object(
+                    &client,
+                    bucket_name,
+                    &crate::r2::prefixed_key(key_prefix.as_deref(), &key),
+                )
+                .await
             }
+            _ => Err("Invalid config type for R2 head".to_string()),
+        }
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn read_book_object(app: AppHandle, key: String) -> Result<ResolvedObject, String> {
+    crate::guard::catch_panic_async(async move {
+        let config = AppConfig::load_from_path(&get_config_path(&app))?;
+        crate::sources::read_object(&config, &key).await
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn fetch_remote_catalog(app: AppHandle) -> Result<Catalog, String> {
+    crate::guard::catch_panic_async(async move {
+        let config = AppConfig::load_from_path(&get_config_path(&app))?;
+        let resolved = crate::sources::read_object(&config, CATALOG_KEY).await?;
+        Catalog::parse(&resolved.data)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn check_book_sources(app: AppHandle) -> Result<Vec<SourceHealth>, String> {
+    crate::guard::catch_panic_async(async move {
+        let task = TaskReporter::start(&app, TaskKind::SourceCheck);
+        let config = match AppConfig::load_from_path(&get_config_path(&app)) {
+            Ok(config) => config,
+            Err(e) => return task.finish(Err(e)),
+        };
+        let sources = config.book_sources();
+        let mut report = Vec::new();
+        for (index, source) in sources.iter().enumerate() {
+            report.push(crate::sources::check_source(index, source, &config).await);
+            task.step(report.len(), sources.len(), None);
+        }
+        task.finish(Ok(report))
+    })
+    .await
+}
 
-            if ssl {
-                let connector = native_tls::TlsConnector::new()
-                    .map_err(|e| format!("Failed to create TLS connector: {}", e))?;
-                let connector = postgres_native_tls::MakeTlsConnector::new(connector);
-                config
-                    .connect(connector)
-                    .map_err(|e| format!("PostgreSQL connection failed: {}", e))?;
-            } else {
-                config
-                    .connect(postgres::NoTls)
-                    .map_err(|e| format!("PostgreSQL connection failed: {}", e))?;
+#[tauri::command]
+pub async fn get_local_sync_state(path: String) -> Result<SyncState, String> {
+    crate::guard::catch_panic_blocking(move || {
+        crate::cloud_folders::scan_sync_state(&PathBuf::from(path))
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn test_postgresql_connection(
+    app: AppHandle,
+    connection: DatabaseConnection,
+) -> Result<String, String> {
+    crate::guard::catch_panic_async(async move {
+        let saved = load_saved_config(&app);
+        crate::faults::inject(&saved.fault_injection, "PostgreSQL").await?;
+        let timeout = saved.timeouts.database();
+        // postgres::Config::connect runs its own runtime with block_on, which
+        // panics inside an async task, so connect from the blocking pool
+        crate::guard::catch_panic_blocking(move || {
+            match connection {
+                DatabaseConnection::PostgreSQL {
+                    host,
+                    port,
+                    user,
+                    password,
+                    database,
+                    ssl,
+                } => {
+                    let mut config = postgres::Config::new();
+                    config
+                        .host(&host)
+                        .port(port)
+                        .user(&user)
+                        .dbname(&database)
+                        .connect_timeout(timeout);
+
+                    if let Some(pwd) = password {
+                        config.password(&pwd);
+                    }
+
+                    if ssl {
+                        let connector = native_tls::TlsConnector::new()
+                            .map_err(|e| format!("Failed to create TLS connector: {}", e))?;
+                        let connector = postgres_native_tls::MakeTlsConnector::new(connector);
+                        config
+                            .connect(connector)
+                            .map_err(|e| format!("PostgreSQL connection failed: {}", e))?;
+                    } else {
+                        config
+                            .connect(postgres::NoTls)
+                            .map_err(|e| format!("PostgreSQL connection failed: {}", e))?;
+                    }
+
+                    Ok("Database connection successful".to_string())
+                }
             }
+        })
+        .await
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn set_reminders(app: AppHandle, reminders: ReminderConfig) -> Result<(), String> {
+    crate::guard::catch_panic(|| {
+        crate::reminders::parse_time(&reminders.time)?;
+        let path = get_config_path(&app);
+        let mut config = AppConfig::load_from_path(&path)?;
+        config.system.reminders = reminders;
+        config.save_to_path(&path)
+    })
+}
 
-            Ok("Database connection successful".to_string())
+#[tauri::command]
+pub fn snooze_reminder(state: State<'_, ReminderState>, minutes: u32) -> Result<(), String> {
+    crate::guard::catch_panic(|| {
+        state.snooze(Local::now().naive_local() + chrono::Duration::minutes(minutes.into()));
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn export_study_plan_ics(app: AppHandle, path: String) -> Result<(), String> {
+    crate::guard::catch_panic(|| {
+        let config = AppConfig::load_from_path(&get_config_path(&app))?;
+        let today = Local::now().date_naive();
+        let ics =
+            crate::calendar::build_study_plan_ics(&config.system.reminders, today, Utc::now())?;
+        std::fs::write(PathBuf::from(path), ics).map_err(|e| e.to_string())
+    })
+}
+
+#[tauri::command]
+pub fn list_crash_reports(app: AppHandle) -> Result<Vec<CrashReport>, String> {
+    crate::guard::catch_panic(|| {
+        crate::crash::list_reports(&get_crash_reports_dir(&app))
+    })
+}
+
+#[tauri::command]
+pub fn delete_crash_report(app: AppHandle, name: String) -> Result<(), String> {
+    crate::guard::catch_panic(|| {
+        crate::crash::delete_report(&get_crash_reports_dir(&app), &name)
+    })
+}
+
+#[tauri::command]
+pub fn record_pronunciation(
+    app: AppHandle,
+    product_code: String,
+    page_label: String,
+    data: Vec<u8>,
+    format: String,
+) -> Result<Recording, String> {
+    crate::guard::catch_panic(|| {
+        crate::recordings::save_recording(
+            &get_recordings_dir(&app),
+            &product_code,
+            &page_label,
+            &data,
+            &format,
+        )
+    })
+}
+
+#[tauri::command]
+pub fn list_pronunciation_recordings(
+    app: AppHandle,
+    product_code: String,
+    page_label: String,
+) -> Result<Vec<Recording>, String> {
+    crate::guard::catch_panic(|| {
+        crate::recordings::list_recordings(&get_recordings_dir(&app), &product_code, &page_label)
+    })
+}
+
+#[tauri::command]
+pub async fn read_pronunciation_recording(
+    app: AppHandle,
+    product_code: String,
+    page_label: String,
+    id: String,
+) -> Result<Vec<u8>, String> {
+    crate::guard::catch_panic_blocking(move || {
+        crate::recordings::read_recording(
+            &get_recordings_dir(&app),
+            &product_code,
+            &page_label,
+            &id,
+        )
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn delete_pronunciation_recording(
+    app: AppHandle,
+    product_code: String,
+    page_label: String,
+    id: String,
+) -> Result<(), String> {
+    crate::guard::catch_panic(|| {
+        crate::recordings::delete_recording(
+            &get_recordings_dir(&app),
+            &product_code,
+            &page_label,
+            &id,
+        )
+    })
+}
+
+#[tauri::command]
+pub async fn migrate_app_data(
+    app: AppHandle,
+    old_path: String,
+) -> Result<MigrationReport, String> {
+    crate::guard::catch_panic_blocking(move || {
+        let task = TaskReporter::start(&app, TaskKind::Migration);
+        let result = crate::migrate::migrate_app_data(
+            &PathBuf::from(old_path),
+            &get_config_path(&app),
+            &get_crash_reports_dir(&app),
+            &get_recordings_dir(&app),
+            |done, total| task.step(done, total, None),
+        );
+        task.finish(result)
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn is_portable_mode() -> bool {
+    crate::paths::portable_dir().is_some()
+}
+
+/// Saves the size and position of every open window right away instead of
+/// waiting for them to close.
+#[tauri::command]
+pub fn save_ui_state(app: AppHandle) -> Result<(), String> {
+    crate::guard::catch_panic(|| {
+        #[cfg(desktop)]
+        {
+            use tauri_plugin_window_state::{AppHandleExt, StateFlags};
+            app.save_window_state(StateFlags::all())
+                .map_err(|e| e.to_string())
+        }
+        #[cfg(mobile)]
+        {
+            let _ = app;
+            Ok(())
+        }
+    })
+}
+
+#[tauri::command]
+pub fn restore_ui_state(window: Window) -> Result<(), String> {
+    crate::guard::catch_panic(|| {
+        #[cfg(desktop)]
+        {
+            use tauri_plugin_window_state::{StateFlags, WindowExt};
+            window
+                .restore_state(StateFlags::all())
+                .map_err(|e| e.to_string())
+        }
+        #[cfg(mobile)]
+        {
+            let _ = window;
+            Ok(())
         }
-    }
+    })
 }
 
 #[tauri::command]
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
 use std::io::Write;
+use std::time::Duration;
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(tag = "type", content = "details")]
@@ -15,6 +16,10 @@ pub enum BookSource {
         access_key_id: String,
         secret_access_key: String,
         public_url: Option<String>,
+        /// Key prefix the credentials are scoped to, e.g. "english/". All keys
+        /// are resolved below it.
+        #[serde(default)]
+        key_prefix: Option<String>,
     },
 }
 
@@ -35,6 +40,11 @@ pub enum DatabaseConnection {
 pub struct SystemConfig {
     pub language: String,
     pub theme: String,
+    #[serde(default)]
+    pub reminders: ReminderConfig,
+    /// Write local crash reports when the app panics.
+    #[serde(default)]
+    pub crash_reporting: bool,
 }
 
 impl Default for SystemConfig {
@@ -42,16 +52,101 @@ impl Default for SystemConfig {
         Self {
             language: "en".to_string(),
             theme: "system".to_string(),
+            reminders: ReminderConfig::default(),
+            crash_reporting: false,
+        }
+    }
+}
+
+/// Daily study reminder schedule.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(default)]
+pub struct ReminderConfig {
+    pub enabled: bool,
+    /// Local time of day in `HH:MM` format.
+    pub time: String,
+    /// ISO weekdays the reminder fires on, 1 = Monday through 7 = Sunday.
+    pub days: Vec<u32>,
+}
+
+impl Default for ReminderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            time: "20:00".to_string(),
+            days: (1..=7).collect(),
+        }
+    }
+}
+
+/// Per-service timeouts, in seconds, applied to remote connection checks.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(default)]
+pub struct TimeoutConfig {
+    pub r2_secs: u64,
+    pub database_secs: u64,
+    /// How long to wait for iCloud to download a placeholder file, which can
+    /// take much longer than a single request for large scans or audio.
+    pub hydration_secs: u64,
+}
+
+/// A zero timeout would make every check fail at once, so it means 1s.
+const MIN_TIMEOUT_SECS: u64 = 1;
+
+impl TimeoutConfig {
+    pub fn r2(&self) -> Duration {
+        Duration::from_secs(self.r2_secs.max(MIN_TIMEOUT_SECS))
+    }
+
+    pub fn database(&self) -> Duration {
+        Duration::from_secs(self.database_secs.max(MIN_TIMEOUT_SECS))
+    }
+
+    pub fn hydration(&self) -> Duration {
+        Duration::from_secs(self.hydration_secs.max(MIN_TIMEOUT_SECS))
+    }
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        Self {
+            r2_secs: 10,
+            database_secs: 10,
+            hydration_secs: 300,
         }
     }
 }
 
+/// Developer setting that makes remote calls slow or flaky on purpose, to
+/// exercise retry and error handling. Disabled unless set in config.toml.
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+#[serde(default)]
+pub struct FaultInjection {
+    /// Chance, from 0 to 100, that a remote call fails.
+    pub failure_percent: u8,
+    /// Delay added before every remote call.
+    pub latency_ms: u64,
+}
+
+impl FaultInjection {
+    pub fn is_disabled(&self) -> bool {
+        self.failure_percent == 0 && self.latency_ms == 0
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
 pub struct AppConfig {
     #[serde(default)]
     pub system: SystemConfig,
     pub book_source: Option<BookSource>,
+    /// Additional sources tried in order when `book_source` can't serve an object.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallback_sources: Vec<BookSource>,
     pub database: Option<DatabaseConnection>,
+    #[serde(default)]
+    pub timeouts: TimeoutConfig,
+    #[serde(default, skip_serializing_if = "FaultInjection::is_disabled")]
+    pub fault_injection: FaultInjection,
 }
 
 impl AppConfig {
@@ -59,6 +154,15 @@ impl AppConfig {
         Self::default()
     }
 
+    /// All configured book sources, highest priority first.
+    pub fn book_sources(&self) -> Vec<BookSource> {
+        self.book_source
+            .iter()
+            .chain(self.fallback_sources.iter())
+            .cloned()
+            .collect()
+    }
+
     pub fn load_from_path(path: &Path) -> Result<Self, String> {
         if !path.exists() {
             return Ok(Self::default());
@@ -90,6 +194,8 @@ mod tests {
         assert_eq!(config.database, None);
         assert_eq!(config.system.language, "en");
         assert_eq!(config.system.theme, "system");
+        assert_eq!(config.timeouts, TimeoutConfig::default());
+        assert!(!config.system.reminders.enabled);
     }
 
     #[test]
@@ -142,12 +248,84 @@ mod tests {
             access_key_id: "key".to_string(),
             secret_access_key: "secret".to_string(),
             public_url: Some("https://pub.url".to_string()),
+            key_prefix: Some("english/".to_string()),
+        });
+
+        config.save_to_path(path).expect("Failed to save config");
+
+        let loaded_config = AppConfig::load_from_path(path).expect("Failed to load config");
+        assert_eq!(config, loaded_config);
+    }
+
+    #[test]
+    fn test_save_and_load_fallback_sources() {
+        let file = NamedTempFile::new().unwrap();
+        let path = file.path();
+
+        let mut config = AppConfig::new();
+        config.book_source = Some(BookSource::Local {
+            path: "/mnt/nas/books".to_string(),
         });
+        config.fallback_sources = vec![BookSource::CloudflareR2 {
+            account_id: "acc_id".to_string(),
+            bucket_name: "my-bucket".to_string(),
+            access_key_id: "key".to_string(),
+            secret_access_key: "secret".to_string(),
+            public_url: None,
+            key_prefix: None,
+        }];
 
         config.save_to_path(path).expect("Failed to save config");
 
         let loaded_config = AppConfig::load_from_path(path).expect("Failed to load config");
         assert_eq!(config, loaded_config);
+
+        let sources = loaded_config.book_sources();
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0], config.book_source.unwrap());
+        assert_eq!(sources[1], config.fallback_sources[0]);
+    }
+
+    #[test]
+    fn test_load_config_with_missing_sections() {
+        let file = NamedTempFile::new().unwrap();
+        let path = file.path();
+        fs::write(path, "[system]\nlanguage = \"zh\"\ntheme = \"dark\"\n").unwrap();
+
+        let config = AppConfig::load_from_path(path).expect("Failed to load config");
+        assert_eq!(config.system.reminders, ReminderConfig::default());
+        assert_eq!(config.timeouts, TimeoutConfig::default());
+        assert_eq!(config.timeouts.r2(), Duration::from_secs(10));
+        assert!(config.fault_injection.is_disabled());
+    }
+
+    #[test]
+    fn test_zero_timeouts_are_clamped() {
+        let timeouts = TimeoutConfig {
+            r2_secs: 0,
+            database_secs: 0,
+            hydration_secs: 0,
+        };
+        assert_eq!(timeouts.r2(), Duration::from_secs(1));
+        assert_eq!(timeouts.database(), Duration::from_secs(1));
+        assert_eq!(timeouts.hydration(), Duration::from_secs(1));
+        assert_eq!(TimeoutConfig::default().r2(), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_fault_injection_only_saved_when_enabled() {
+        let file = NamedTempFile::new().unwrap();
+        let path = file.path();
+
+        let mut config = AppConfig::new();
+        config.save_to_path(path).expect("Failed to save config");
+        assert!(!fs::read_to_string(path).unwrap().contains("fault_injection"));
+
+        config.fault_injection.failure_percent = 25;
+        config.save_to_path(path).expect("Failed to save config");
+        let loaded_config = AppConfig::load_from_path(path).expect("Failed to load config");
+        assert_eq!(loaded_config.fault_injection.failure_percent, 25);
+        assert_eq!(loaded_config.fault_injection.latency_ms, 0);
     }
 
     #[test]
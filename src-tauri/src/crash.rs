@@ -0,0 +1,184 @@
+use crate::config::{AppConfig, BookSource, DatabaseConnection};
+use chrono::Local;
+use serde::Serialize;
+use std::backtrace::Backtrace;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const REPORT_PREFIX: &str = "crash-";
+const REPORT_EXTENSION: &str = "txt";
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct CrashReport {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+}
+
+/// A short description of the app state for crash reports. Only records
+/// which kinds of sources are configured, never paths, hosts or keys. The
+/// rest of a report can still contain paths, see [`install_panic_hook`].
+pub fn state_summary(config: &AppConfig) -> String {
+    let sources: Vec<&str> = config
+        .book_sources()
+        .iter()
+        .map(|source| match source {
+            BookSource::Local { .. } => "Local",
+            BookSource::CloudflareR2 { .. } => "CloudflareR2",
+        })
+        .collect();
+    let database = match &config.database {
+        Some(DatabaseConnection::PostgreSQL { .. }) => "PostgreSQL",
+        None => "none",
+    };
+
+    format!(
+        "version: {}\nos: {} ({})\nlanguage: {}\ntheme: {}\nbook sources: [{}]\ndatabase: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        config.system.language,
+        config.system.theme,
+        sources.join(", "),
+        database,
+    )
+}
+
+pub fn write_report(dir: &Path, contents: &str) -> Result<PathBuf, String> {
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let name = format!(
+        "{}{}.{}",
+        REPORT_PREFIX,
+        Local::now().format("%Y%m%d-%H%M%S-%3f"),
+        REPORT_EXTENSION
+    );
+    let path = dir.join(name);
+    fs::write(&path, contents).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// Replaces the user's home directory with `~` so reports don't reveal the
+/// account name.
+fn scrub_home(text: &str, home: Option<&str>) -> String {
+    match home {
+        Some(home) => text.replace(home, "~"),
+        None => text.to_string(),
+    }
+}
+
+/// Writes a report for every panic into `dir`, then runs the previous hook.
+///
+/// Panic messages and backtraces can contain file paths (from error
+/// messages or the build machine). The home directory is replaced with `~`,
+/// but other paths are kept as they are.
+pub fn install_panic_hook(dir: PathBuf, summary: String) {
+    let home = crate::paths::home_dir().map(|home| home.to_string_lossy().into_owned());
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let thread = std::thread::current();
+        let contents = format!(
+            "time: {}\nthread: {}\npanic: {}\n\n{}\nbacktrace:\n{}\n",
+            Local::now().to_rfc3339(),
+            thread.name().unwrap_or("<unnamed>"),
+            info,
+            summary,
+            Backtrace::force_capture(),
+        );
+        let _ = write_report(&dir, &scrub_home(&contents, home.as_deref()));
+        previous(info);
+    }));
+}
+
+fn is_report_name(name: &str) -> bool {
+    let path = Path::new(name);
+    path.file_name().and_then(|n| n.to_str()) == Some(name)
+        && name.starts_with(REPORT_PREFIX)
+        && path.extension().and_then(|e| e.to_str()) == Some(REPORT_EXTENSION)
+}
+
+pub fn list_reports(dir: &Path) -> Result<Vec<CrashReport>, String> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut reports = Vec::new();
+    for entry in fs::read_dir(dir).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !is_report_name(&name) {
+            continue;
+        }
+        let size = entry.metadata().map(|m| m.len()).unwrap_or_default();
+        reports.push(CrashReport {
+            path: entry.path().to_string_lossy().to_string(),
+            name,
+            size,
+        });
+    }
+    // Names embed the timestamp, so newest first is reverse name order
+    reports.sort_by(|a, b| b.name.cmp(&a.name));
+    Ok(reports)
+}
+
+pub fn delete_report(dir: &Path, name: &str) -> Result<(), String> {
+    if !is_report_name(name) {
+        return Err(format!("Invalid crash report name: {}", name));
+    }
+    fs::remove_file(dir.join(name)).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_state_summary_omits_credentials() {
+        let mut config = AppConfig::new();
+        config.book_source = Some(BookSource::CloudflareR2 {
+            account_id: "acc_id".to_string(),
+            bucket_name: "my-bucket".to_string(),
+            access_key_id: "key".to_string(),
+            secret_access_key: "secret".to_string(),
+            public_url: None,
+            key_prefix: None,
+        });
+
+        let summary = state_summary(&config);
+        assert!(summary.contains("book sources: [CloudflareR2]"));
+        assert!(summary.contains("database: none"));
+        assert!(!summary.contains("my-bucket"));
+        assert!(!summary.contains("secret"));
+    }
+
+    #[test]
+    fn test_scrub_home() {
+        assert_eq!(
+            scrub_home(
+                "Failed to read /home/alice/books/a.pdf",
+                Some("/home/alice")
+            ),
+            "Failed to read ~/books/a.pdf"
+        );
+        assert_eq!(scrub_home("boom", None), "boom");
+    }
+
+    #[test]
+    fn test_write_list_and_delete_reports() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("notes.txt"), "not a report").unwrap();
+
+        let path = write_report(dir.path(), "panic: boom").unwrap();
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+
+        let reports = list_reports(dir.path()).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].name, name);
+        assert_eq!(reports[0].size, "panic: boom".len() as u64);
+
+        assert!(delete_report(dir.path(), "notes.txt").is_err());
+        assert!(delete_report(dir.path(), "../crash-x.txt").is_err());
+        delete_report(dir.path(), &name).unwrap();
+        assert!(list_reports(dir.path()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,55 @@
+use crate::config::FaultInjection;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// A random percentage in `0..100`. Each `RandomState` is seeded differently,
+/// which is plenty for deciding whether to inject a fault.
+fn random_percent() -> u64 {
+    RandomState::new().build_hasher().finish() % 100
+}
+
+/// Applies the configured artificial latency and failure rate to a remote call.
+/// A no-op unless fault injection is enabled in the developer config.
+pub async fn inject(faults: &FaultInjection, service: &str) -> Result<(), String> {
+    if faults.latency_ms > 0 {
+        tokio::time::sleep(Duration::from_millis(faults.latency_ms)).await;
+    }
+    if random_percent() < u64::from(faults.failure_percent) {
+        return Err(format!("{} call failed (injected fault)", service));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn test_inject_failure_rate_bounds() {
+        let never = FaultInjection::default();
+        let always = FaultInjection {
+            failure_percent: 100,
+            latency_ms: 0,
+        };
+        for _ in 0..50 {
+            assert!(inject(&never, "R2").await.is_ok());
+            assert_eq!(
+                inject(&always, "R2").await,
+                Err("R2 call failed (injected fault)".to_string())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_inject_latency() {
+        let faults = FaultInjection {
+            failure_percent: 0,
+            latency_ms: 20,
+        };
+        let started = Instant::now();
+        inject(&faults, "PostgreSQL").await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(20));
+    }
+}
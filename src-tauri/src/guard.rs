@@ -0,0 +1,102 @@
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use tokio::task::JoinError;
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+fn internal_error(payload: &(dyn Any + Send)) -> String {
+    format!("Internal error: {}", panic_message(payload))
+}
+
+/// Makes every panic log a backtrace to stderr. The default hook only prints
+/// one when `RUST_BACKTRACE` is set, so this forces it otherwise.
+pub fn install_backtrace_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        previous(info);
+        let enabled = std::env::var_os("RUST_BACKTRACE").is_some_and(|v| v != "0");
+        if !enabled {
+            eprintln!("stack backtrace:\n{}", Backtrace::force_capture());
+        }
+    }));
+}
+
+/// Runs a synchronous command body, turning a panic into an error for the
+/// frontend. The panic hook still runs first, so the panic and its backtrace
+/// are logged (and written to a crash report when enabled).
+pub fn catch_panic<T>(body: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(body))
+        .unwrap_or_else(|payload| Err(internal_error(&*payload)))
+}
+
+/// Async counterpart of [`catch_panic`]: the body runs as its own task so a
+/// panic is reported through the join handle instead of killing the IPC call.
+pub async fn catch_panic_async<T, F>(body: F) -> Result<T, String>
+where
+    F: Future<Output = Result<T, String>> + Send + 'static,
+    T: Send + 'static,
+{
+    joined(tokio::spawn(body).await)
+}
+
+/// Like [`catch_panic`], but runs the body on the blocking thread pool. Use it
+/// for commands that do heavy file I/O, since sync commands run on the main
+/// thread and would freeze the UI until they return.
+pub async fn catch_panic_blocking<T, F>(body: F) -> Result<T, String>
+where
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+    T: Send + 'static,
+{
+    joined(tokio::task::spawn_blocking(body).await)
+}
+
+fn joined<T>(result: Result<Result<T, String>, JoinError>) -> Result<T, String> {
+    match result {
+        Ok(result) => result,
+        Err(e) if e.is_panic() => Err(internal_error(&*e.into_panic())),
+        Err(e) => Err(format!("Internal error: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catch_panic() {
+        assert_eq!(catch_panic(|| Ok(1)), Ok(1));
+        assert_eq!(
+            catch_panic::<()>(|| Err("failed".to_string())),
+            Err("failed".to_string())
+        );
+
+        let result = catch_panic::<()>(|| panic!("boom {}", 42));
+        assert_eq!(result, Err("Internal error: boom 42".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_catch_panic_async() {
+        assert_eq!(catch_panic_async(async { Ok(1) }).await, Ok(1));
+
+        let result = catch_panic_async::<(), _>(async { panic!("boom") }).await;
+        assert_eq!(result, Err("Internal error: boom".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_catch_panic_blocking() {
+        assert_eq!(catch_panic_blocking(|| Ok(1)).await, Ok(1));
+
+        let result = catch_panic_blocking::<(), _>(|| panic!("boom")).await;
+        assert_eq!(result, Err("Internal error: boom".to_string()));
+    }
+}
@@ -1,8 +1,22 @@
+pub mod calendar;
+pub mod catalog;
+pub mod cloud_folders;
 pub mod config;
 pub mod commands;
+pub mod crash;
+pub mod faults;
+pub mod guard;
+pub mod migrate;
+pub mod paths;
+pub mod progress;
 pub mod r2;
+pub mod recordings;
+pub mod reminders;
+pub mod sources;
 
+#[cfg(desktop)]
 use tauri::menu::{Menu, MenuItem, Submenu, PredefinedMenuItem};
+#[cfg(desktop)]
 use tauri::Emitter;
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
@@ -11,48 +25,90 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+#[cfg(desktop)]
+fn setup_menu(app: &tauri::App) -> tauri::Result<()> {
+    let handle = app.handle();
+
+    let settings_item = MenuItem::with_id(handle, "settings", "Settings...", true, Some("CmdOrCtrl+,"))?;
+    let quit_item = PredefinedMenuItem::quit(handle, None)?;
+    
+    let app_submenu = Submenu::with_items(
+        handle,
+        "App",
+        true,
+        &[&settings_item, &PredefinedMenuItem::separator(handle)?, &quit_item],
+    )?;
+
+    let edit_submenu = Submenu::with_items(
+        handle,
+        "Edit",
+        true,
+        &[
+            &PredefinedMenuItem::undo(handle, None)?,
+            &PredefinedMenuItem::redo(handle, None)?,
+            &PredefinedMenuItem::separator(handle)?,
+            &PredefinedMenuItem::cut(handle, None)?,
+            &PredefinedMenuItem::copy(handle, None)?,
+            &PredefinedMenuItem::paste(handle, None)?,
+            &PredefinedMenuItem::separator(handle)?,
+            &PredefinedMenuItem::select_all(handle, None)?,
+        ],
+    )?;
+
+    let menu = Menu::with_items(handle, &[&app_submenu, &edit_submenu])?;
+    app.set_menu(menu)?;
+
+    app.on_menu_event(move |app, event| {
+        if event.id == "settings" {
+            let _ = app.emit("open-settings", ());
+        }
+    });
+    Ok(())
+}
+
+/// Restores every window's size and position on launch and saves them again
+/// on close. It has to be registered on the builder: windows from the config
+/// are created before `setup` runs, and a plugin added later never sees them.
+#[cfg(desktop)]
+fn window_state_plugin<R: tauri::Runtime>() -> tauri::plugin::TauriPlugin<R> {
+    let mut builder = tauri_plugin_window_state::Builder::default();
+    if let Some(dir) = paths::portable_dir() {
+        // The plugin joins the file name onto the platform config dir, and an
+        // absolute path replaces that base. It only creates the platform dir,
+        // so make sure ours exists before the first save.
+        let _ = std::fs::create_dir_all(dir);
+        builder = builder.with_filename(dir.join(".window-state.json").to_string_lossy());
+    }
+    builder.build()
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let builder = tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init());
+    #[cfg(desktop)]
+    let builder = builder.plugin(window_state_plugin());
+
+    builder
+        .manage(reminders::ReminderState::default())
         .setup(|app| {
             let handle = app.handle();
-            
-            let settings_item = MenuItem::with_id(handle, "settings", "Settings...", true, Some("CmdOrCtrl+,"))?;
-            let quit_item = PredefinedMenuItem::quit(handle, None)?;
-            
-            let app_submenu = Submenu::with_items(
-                handle,
-                "App",
-                true,
-                &[&settings_item, &PredefinedMenuItem::separator(handle)?, &quit_item],
-            )?;
-
-            let edit_submenu = Submenu::with_items(
-                handle,
-                "Edit",
-                true,
-                &[
-                    &PredefinedMenuItem::undo(handle, None)?,
-                    &PredefinedMenuItem::redo(handle, None)?,
-                    &PredefinedMenuItem::separator(handle)?,
-                    &PredefinedMenuItem::cut(handle, None)?,
-                    &PredefinedMenuItem::copy(handle, None)?,
-                    &PredefinedMenuItem::paste(handle, None)?,
-                    &PredefinedMenuItem::separator(handle)?,
-                    &PredefinedMenuItem::select_all(handle, None)?,
-                ],
-            )?;
-
-            let menu = Menu::with_items(handle, &[&app_submenu, &edit_submenu])?;
-            app.set_menu(menu)?;
+            let config = config::AppConfig::load_from_path(&commands::get_config_path(handle))
+                .unwrap_or_default();
+            guard::install_backtrace_hook();
+            if config.system.crash_reporting {
+                crash::install_panic_hook(
+                    commands::get_crash_reports_dir(handle),
+                    crash::state_summary(&config),
+                );
+            }
+            reminders::spawn_scheduler(handle.clone());
 
-            app.on_menu_event(move |app, event| {
-                if event.id == "settings" {
-                    let _ = app.emit("open-settings", ());
-                }
-            });
+            // Window menus only exist on desktop platforms
+            #[cfg(desktop)]
+            setup_menu(app)?;
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -64,7 +120,25 @@ pub fn run() {
             commands::test_r2_connection,
             commands::list_r2_objects,
             commands::read_r2_object,
+            commands::head_r2_object,
+            commands::read_book_object,
+            commands::check_book_sources,
+            commands::fetch_remote_catalog,
+            commands::get_local_sync_state,
             commands::test_postgresql_connection,
+            commands::set_reminders,
+            commands::snooze_reminder,
+            commands::export_study_plan_ics,
+            commands::list_crash_reports,
+            commands::delete_crash_report,
+            commands::record_pronunciation,
+            commands::list_pronunciation_recordings,
+            commands::read_pronunciation_recording,
+            commands::delete_pronunciation_recording,
+            commands::migrate_app_data,
+            commands::is_portable_mode,
+            commands::save_ui_state,
+            commands::restore_ui_state,
             commands::restart
         ])
         .run(tauri::generate_context!())
@@ -0,0 +1,210 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the config file inside the config directory.
+pub const CONFIG_FILE: &str = "config.toml";
+/// Name of the crash report folder inside the data directory.
+pub const CRASH_REPORTS_DIR: &str = "crash_reports";
+/// Name of the pronunciation recordings folder inside the data directory.
+pub const RECORDINGS_DIR: &str = "recordings";
+
+#[derive(Debug, Serialize, PartialEq, Default)]
+pub struct MigrationReport {
+    pub moved: Vec<String>,
+    pub skipped: Vec<String>,
+    /// Problems that didn't stop the migration, such as an original that
+    /// couldn't be removed after it was copied.
+    pub warnings: Vec<String>,
+}
+
+fn copy_recursive(from: &Path, to: &Path) -> Result<(), String> {
+    if from.is_dir() {
+        fs::create_dir_all(to).map_err(|e| e.to_string())?;
+        for entry in fs::read_dir(from).map_err(|e| e.to_string())? {
+            let entry = entry.map_err(|e| e.to_string())?;
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::copy(from, to).map(|_| ()).map_err(|e| e.to_string())
+    }
+}
+
+fn verify_copy(from: &Path, to: &Path) -> Result<(), String> {
+    if from.is_dir() {
+        for entry in fs::read_dir(from).map_err(|e| e.to_string())? {
+            let entry = entry.map_err(|e| e.to_string())?;
+            verify_copy(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        let original = fs::read(from).map_err(|e| e.to_string())?;
+        let copied = fs::read(to).map_err(|e| e.to_string())?;
+        if original == copied {
+            Ok(())
+        } else {
+            Err(format!(
+                "Copy of {} does not match the original",
+                from.display()
+            ))
+        }
+    }
+}
+
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+/// Moves app data from `old_dir` to the given targets. Every item is copied and
+/// verified before any original is removed; if a copy fails, the copies made
+/// so far are deleted and the old directory is left untouched. Once all copies
+/// are verified the originals are removed on a best-effort basis, and any that
+/// can't be removed are listed in `warnings`.
+///
+/// `on_progress` is called with the number of items copied so far and the
+/// number of items to copy.
+pub fn migrate_app_data(
+    old_dir: &Path,
+    config_path: &Path,
+    crash_reports_dir: &Path,
+    recordings_dir: &Path,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<MigrationReport, String> {
+    if !old_dir.is_dir() {
+        return Err(format!("Not a directory: {}", old_dir.display()));
+    }
+
+    let items: Vec<(PathBuf, &Path)> = vec![
+        (old_dir.join(CONFIG_FILE), config_path),
+        (old_dir.join(CRASH_REPORTS_DIR), crash_reports_dir),
+        (old_dir.join(RECORDINGS_DIR), recordings_dir),
+    ];
+
+    let mut report = MigrationReport::default();
+    let mut pending = Vec::new();
+    for (from, to) in items {
+        if !from.exists() || from == to {
+            report.skipped.push(from.display().to_string());
+        } else if to.exists() {
+            return Err(format!(
+                "{} already exists; remove it before migrating",
+                to.display()
+            ));
+        } else {
+            pending.push((from, to));
+        }
+    }
+
+    let mut copied: Vec<&Path> = Vec::new();
+    on_progress(0, pending.len());
+    for (from, to) in &pending {
+        let result = copy_recursive(from, to).and_then(|_| verify_copy(from, to));
+        if let Err(e) = result {
+            let _ = remove_path(to);
+            for path in copied {
+                let _ = remove_path(path);
+            }
+            return Err(format!("Migration rolled back: {}", e));
+        }
+        copied.push(to);
+        on_progress(copied.len(), pending.len());
+    }
+
+    for (from, _) in pending {
+        // The verified copy is in place, so the item counts as moved either way
+        if let Err(e) = remove_path(&from) {
+            report.warnings.push(format!(
+                "Could not remove {} after copying it: {}",
+                from.display(),
+                e
+            ));
+        }
+        report.moved.push(from.display().to_string());
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_migrate_moves_config_and_reports() {
+        let old = TempDir::new().unwrap();
+        let new = TempDir::new().unwrap();
+        fs::write(old.path().join(CONFIG_FILE), "[system]\n").unwrap();
+        fs::create_dir_all(old.path().join(CRASH_REPORTS_DIR)).unwrap();
+        fs::write(
+            old.path().join(CRASH_REPORTS_DIR).join("crash-1.txt"),
+            "boom",
+        )
+        .unwrap();
+        let recording = Path::new(RECORDINGS_DIR)
+            .join("egu-int")
+            .join("42")
+            .join("recording-1.webm");
+        fs::create_dir_all(old.path().join(recording.parent().unwrap())).unwrap();
+        fs::write(old.path().join(&recording), "audio").unwrap();
+
+        let config_path = new.path().join("config").join(CONFIG_FILE);
+        let reports_dir = new.path().join("data").join(CRASH_REPORTS_DIR);
+        let recordings_dir = new.path().join("data").join(RECORDINGS_DIR);
+        let mut steps = Vec::new();
+        let report = migrate_app_data(
+            old.path(),
+            &config_path,
+            &reports_dir,
+            &recordings_dir,
+            |done, total| steps.push((done, total)),
+        )
+        .unwrap();
+
+        assert_eq!(report.moved.len(), 3);
+        assert!(report.warnings.is_empty());
+        assert_eq!(steps, vec![(0, 3), (1, 3), (2, 3), (3, 3)]);
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), "[system]\n");
+        assert_eq!(
+            fs::read_to_string(reports_dir.join("crash-1.txt")).unwrap(),
+            "boom"
+        );
+        assert!(!old.path().join(CONFIG_FILE).exists());
+        assert_eq!(
+            fs::read_to_string(new.path().join("data").join(&recording)).unwrap(),
+            "audio"
+        );
+        assert!(!old.path().join(CRASH_REPORTS_DIR).exists());
+        assert!(!old.path().join(RECORDINGS_DIR).exists());
+    }
+
+    #[test]
+    fn test_migrate_refuses_to_overwrite() {
+        let old = TempDir::new().unwrap();
+        let new = TempDir::new().unwrap();
+        fs::write(old.path().join(CONFIG_FILE), "old").unwrap();
+        let config_path = new.path().join(CONFIG_FILE);
+        fs::write(&config_path, "current").unwrap();
+
+        let result = migrate_app_data(
+            old.path(),
+            &config_path,
+            &new.path().join(CRASH_REPORTS_DIR),
+            &new.path().join(RECORDINGS_DIR),
+            |_, _| {},
+        );
+        assert!(result.is_err());
+        assert_eq!(
+            fs::read_to_string(old.path().join(CONFIG_FILE)).unwrap(),
+            "old"
+        );
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), "current");
+    }
+}
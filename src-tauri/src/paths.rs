@@ -0,0 +1,96 @@
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+
+/// Marker file next to the executable that turns on portable mode.
+pub const PORTABLE_FLAG: &str = "portable.flag";
+/// Command-line switch that turns on portable mode.
+pub const PORTABLE_ARG: &str = "--portable";
+/// Folder beside the executable holding all app data in portable mode.
+pub const PORTABLE_DATA_DIR: &str = "data";
+
+/// Returns the portable data folder if the flag file sits next to `exe` or
+/// the portable switch was passed on the command line.
+pub fn detect_portable_dir<I>(exe: &Path, args: I) -> Option<PathBuf>
+where
+    I: IntoIterator<Item = OsString>,
+{
+    let exe_dir = exe.parent()?;
+    let enabled = exe_dir.join(PORTABLE_FLAG).exists()
+        || args.into_iter().any(|arg| arg == OsStr::new(PORTABLE_ARG));
+    enabled.then(|| exe_dir.join(PORTABLE_DATA_DIR))
+}
+
+/// The user's home directory, from `HOME` or `USERPROFILE` on Windows.
+pub fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+}
+
+/// The portable data folder for this process, resolved once at first use.
+/// Mobile apps live in a sandbox with no folder beside the binary, so
+/// portable mode is desktop-only.
+pub fn portable_dir() -> Option<&'static Path> {
+    if cfg!(mobile) {
+        return None;
+    }
+
+    static PORTABLE_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+    PORTABLE_DIR
+        .get_or_init(|| {
+            let exe = std::env::current_exe().ok()?;
+            // args() would panic on arguments that aren't valid Unicode
+            detect_portable_dir(&exe, std::env::args_os().skip(1))
+        })
+        .as_deref()
+}
+
+pub fn app_config_dir(app: &AppHandle) -> PathBuf {
+    // In a real app, you might want to handle errors better than unwrap
+    // but for now, we assume the app config dir is always available.
+    match portable_dir() {
+        Some(dir) => dir.to_path_buf(),
+        None => app
+            .path()
+            .app_config_dir()
+            .expect("Could not resolve app config dir"),
+    }
+}
+
+pub fn app_data_dir(app: &AppHandle) -> PathBuf {
+    match portable_dir() {
+        Some(dir) => dir.to_path_buf(),
+        None => app
+            .path()
+            .app_data_dir()
+            .expect("Could not resolve app data dir"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_portable_dir() {
+        let dir = TempDir::new().unwrap();
+        let exe = dir.path().join("english-in-use");
+
+        assert_eq!(detect_portable_dir(&exe, Vec::new()), None);
+        assert_eq!(
+            detect_portable_dir(&exe, vec![OsString::from(PORTABLE_ARG)]),
+            Some(dir.path().join(PORTABLE_DATA_DIR))
+        );
+
+        fs::write(dir.path().join(PORTABLE_FLAG), "").unwrap();
+        assert_eq!(
+            detect_portable_dir(&exe, Vec::new()),
+            Some(dir.path().join(PORTABLE_DATA_DIR))
+        );
+    }
+}
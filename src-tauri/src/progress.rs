@@ -0,0 +1,206 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+/// Event every long-running task reports its progress on.
+pub const TASK_PROGRESS_EVENT: &str = "task-progress";
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskKind {
+    Migration,
+    SourceCheck,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskPhase {
+    Started,
+    Running,
+    Finished,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct TaskProgress {
+    pub task_id: String,
+    pub kind: TaskKind,
+    pub phase: TaskPhase,
+    /// 0 to 100, or `None` while the amount of work is unknown.
+    pub percent: Option<f32>,
+    pub message: Option<String>,
+    pub eta_secs: Option<u64>,
+    /// Units of work (items or bytes, depending on the task) per second.
+    pub rate_per_sec: Option<f64>,
+}
+
+/// Weight of the latest step in the moving averages.
+const SMOOTHING: f64 = 0.3;
+
+/// Estimates the remaining time from an exponential moving average of the
+/// recent throughput, so one slow or fast step doesn't swing the ETA.
+struct EtaEstimator {
+    last_at: Instant,
+    last_done: usize,
+    rate: Option<f64>,
+}
+
+impl EtaEstimator {
+    fn new(now: Instant) -> Self {
+        Self {
+            last_at: now,
+            last_done: 0,
+            rate: None,
+        }
+    }
+
+    /// Returns the remaining seconds and the smoothed rate.
+    fn update(&mut self, now: Instant, done: usize, total: usize) -> (Option<u64>, Option<f64>) {
+        let elapsed = now.saturating_duration_since(self.last_at);
+        if done > self.last_done && elapsed > Duration::ZERO {
+            let rate = (done - self.last_done) as f64 / elapsed.as_secs_f64();
+            self.rate = Some(match self.rate {
+                Some(previous) => SMOOTHING * rate + (1.0 - SMOOTHING) * previous,
+                None => rate,
+            });
+            self.last_at = now;
+            self.last_done = done;
+        }
+        let eta = self
+            .rate
+            .map(|rate| (total.saturating_sub(done) as f64 / rate).ceil() as u64);
+        (eta, self.rate)
+    }
+}
+
+/// Emits the progress of one task run. All events of a run share the same
+/// `task_id`.
+///
+/// Only use it from async commands or from work on the blocking pool (see
+/// `guard::catch_panic_blocking`). Sync commands run on the main thread, and
+/// the webview can't draw the events they send until the command returns.
+pub struct TaskReporter {
+    app: AppHandle,
+    task_id: String,
+    kind: TaskKind,
+    eta: Mutex<EtaEstimator>,
+}
+
+impl TaskReporter {
+    pub fn start(app: &AppHandle, kind: TaskKind) -> Self {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        let reporter = Self {
+            app: app.clone(),
+            task_id: format!("{}-{}", kind.as_str(), millis),
+            kind,
+            eta: Mutex::new(EtaEstimator::new(Instant::now())),
+        };
+        reporter.emit(TaskPhase::Started, Some(0.0), None, (None, None));
+        reporter
+    }
+
+    pub fn step(&self, done: usize, total: usize, message: Option<String>) {
+        let estimate = self
+            .eta
+            .lock()
+            .map(|mut eta| eta.update(Instant::now(), done, total))
+            .unwrap_or((None, None));
+        self.emit(TaskPhase::Running, percent(done, total), message, estimate);
+    }
+
+    /// Reports the end of the task and passes the result through.
+    pub fn finish<T>(self, result: Result<T, String>) -> Result<T, String> {
+        match &result {
+            Ok(_) => self.emit(TaskPhase::Finished, Some(100.0), None, (Some(0), None)),
+            Err(e) => self.emit(TaskPhase::Failed, None, Some(e.clone()), (None, None)),
+        }
+        result
+    }
+
+    fn emit(
+        &self,
+        phase: TaskPhase,
+        percent: Option<f32>,
+        message: Option<String>,
+        (eta_secs, rate_per_sec): (Option<u64>, Option<f64>),
+    ) {
+        let _ = self.app.emit(
+            TASK_PROGRESS_EVENT,
+            TaskProgress {
+                task_id: self.task_id.clone(),
+                kind: self.kind,
+                phase,
+                percent,
+                message,
+                eta_secs,
+                rate_per_sec,
+            },
+        );
+    }
+}
+
+impl TaskKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TaskKind::Migration => "migration",
+            TaskKind::SourceCheck => "source_check",
+        }
+    }
+}
+
+fn percent(done: usize, total: usize) -> Option<f32> {
+    if total == 0 {
+        None
+    } else {
+        Some(done.min(total) as f32 * 100.0 / total as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent() {
+        assert_eq!(percent(0, 0), None);
+        assert_eq!(percent(1, 4), Some(25.0));
+        assert_eq!(percent(5, 4), Some(100.0));
+    }
+
+    #[test]
+    fn test_eta_uses_smoothed_rate() {
+        let start = Instant::now();
+        let mut eta = EtaEstimator::new(start);
+        assert_eq!(eta.update(start, 0, 10), (None, None));
+
+        // 2 items in 1s, then 1 item in 1s: 0.3 * 1 + 0.7 * 2 = 1.7 items/s
+        assert_eq!(
+            eta.update(start + Duration::from_secs(1), 2, 10),
+            (Some(4), Some(2.0))
+        );
+        let (remaining, rate) = eta.update(start + Duration::from_secs(2), 3, 10);
+        assert!((rate.unwrap() - 1.7).abs() < 1e-9);
+        assert_eq!(remaining, Some(5));
+    }
+
+    #[test]
+    fn test_progress_serializes_kind_and_phase_in_snake_case() {
+        let progress = TaskProgress {
+            task_id: "source_check-1".to_string(),
+            kind: TaskKind::SourceCheck,
+            phase: TaskPhase::Running,
+            percent: Some(50.0),
+            message: None,
+            eta_secs: None,
+            rate_per_sec: None,
+        };
+        let json = serde_json::to_value(&progress).unwrap();
+        assert_eq!(json["kind"], "source_check");
+        assert_eq!(json["phase"], "running");
+        assert_eq!(json["percent"], 50.0);
+    }
+}
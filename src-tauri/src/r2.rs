@@ -1,9 +1,52 @@
+use aws_config::timeout::TimeoutConfig;
 use aws_config::Region;
+use aws_sdk_s3::config::http::HttpResponse;
 use aws_sdk_s3::config::{Credentials, SharedCredentialsProvider};
+use aws_sdk_s3::error::{DisplayErrorContext, SdkError};
+use aws_sdk_s3::primitives::DateTimeFormat;
+use aws_sdk_s3::types::Object;
 use aws_sdk_s3::Client;
-use crate::config::BookSource;
+use crate::config::{BookSource, FaultInjection};
+use serde::Serialize;
+use std::time::Duration;
 
-pub async fn create_r2_client(source: &BookSource) -> Result<Client, String> {
+/// Object metadata returned by a HEAD request, without downloading the body.
+#[derive(Debug, Serialize, PartialEq, Clone)]
+pub struct ObjectMetadata {
+    pub size: i64,
+    pub etag: Option<String>,
+    /// RFC 3339 timestamp
+    pub last_modified: Option<String>,
+}
+
+/// Resolves `key` below the source's key prefix. Prefixes are treated as
+/// folders, so "english" and "english/" are the same.
+pub fn prefixed_key(prefix: Option<&str>, key: &str) -> String {
+    match prefix.map(|p| p.trim_matches('/')).filter(|p| !p.is_empty()) {
+        Some(prefix) => format!("{}/{}", prefix, key.trim_start_matches('/')),
+        None => key.to_string(),
+    }
+}
+
+/// An R2 client that applies developer fault injection before every request
+/// it sends, so `failure_percent` is a per-request rate.
+pub struct R2Client {
+    client: Client,
+    faults: FaultInjection,
+}
+
+impl R2Client {
+    async fn inject_faults(&self) -> Result<(), String> {
+        crate::faults::inject(&self.faults, "R2").await
+    }
+}
+
+/// Creates a client for a single R2 operation.
+pub async fn create_r2_client(
+    source: &BookSource,
+    timeout: Duration,
+    faults: &FaultInjection,
+) -> Result<R2Client, String> {
     if let BookSource::CloudflareR2 {
         account_id,
         access_key_id,
@@ -24,6 +67,12 @@ pub async fn create_r2_client(source: &BookSource) -> Result<Client, String> {
             .region(Region::new("auto"))
             .endpoint_url(endpoint)
             .credentials_provider(SharedCredentialsProvider::new(credentials))
+            .timeout_config(
+                TimeoutConfig::builder()
+                    .connect_timeout(timeout)
+                    .operation_timeout(timeout)
+                    .build(),
+            )
             .load()
             .await;
 
@@ -31,16 +80,227 @@ pub async fn create_r2_client(source: &BookSource) -> Result<Client, String> {
             .force_path_style(true)
             .build();
 
-        Ok(Client::from_conf(s3_config))
+        Ok(R2Client {
+            client: Client::from_conf(s3_config),
+            faults: faults.clone(),
+        })
     } else {
         Err("Invalid BookSource type".to_string())
     }
 }
 
-pub async fn list_objects(client: &Client, bucket: &str) -> Result<Vec<String>, String> {
+#[derive(Debug, Serialize, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum OperationStatus {
+    Allowed,
+    Denied,
+    /// The probe object doesn't exist, so the permission couldn't be checked.
+    Unverified,
+    /// The request failed for a reason other than permissions (network,
+    /// timeout, server error), so the permission is unknown.
+    Failed,
+}
+
+/// A failed probe request. `denied` is only set for 401/403 responses, so
+/// network and server failures aren't reported as missing permissions.
+#[derive(Debug, PartialEq)]
+struct ProbeError {
+    denied: bool,
+    message: String,
+}
+
+impl ProbeError {
+    fn other(message: String) -> Self {
+        Self {
+            denied: false,
+            message,
+        }
+    }
+
+    fn from_sdk<E: std::error::Error + 'static>(error: SdkError<E, HttpResponse>) -> Self {
+        let status = error.raw_response().map(|r| r.status().as_u16());
+        Self {
+            denied: matches!(status, Some(401 | 403)),
+            message: DisplayErrorContext(&error).to_string(),
+        }
+    }
+
+    fn status(&self) -> OperationStatus {
+        if self.denied {
+            OperationStatus::Denied
+        } else {
+            OperationStatus::Failed
+        }
+    }
+}
+
+/// Which operations the configured credentials are allowed to perform.
+#[derive(Debug, Serialize, PartialEq, Clone)]
+pub struct R2Permissions {
+    pub list: OperationStatus,
+    pub get: OperationStatus,
+    /// Key used for the ranged GET probe.
+    pub probe_key: String,
+    pub list_error: Option<String>,
+    pub get_error: Option<String>,
+    /// Whether the check shows the source can serve books: GET worked, or
+    /// there was nothing to read but listing worked.
+    pub verified: bool,
+}
+
+impl R2Permissions {
+    /// Fails only when no operation works at all, since a token scoped to
+    /// reads may be denied listing and still serve books.
+    fn from_probes(
+        list: Result<(), ProbeError>,
+        get: Result<bool, ProbeError>,
+        probe_key: String,
+    ) -> Result<Self, String> {
+        if let (Err(list_error), Err(get_error)) = (&list, &get) {
+            return Err(format!(
+                "R2 connection failed: {}; GET {}: {}",
+                list_error.message, probe_key, get_error.message
+            ));
+        }
+        let (list, list_error) = match list {
+            Ok(()) => (OperationStatus::Allowed, None),
+            Err(e) => (e.status(), Some(e.message)),
+        };
+        let (get, get_error) = match get {
+            Ok(true) => (OperationStatus::Allowed, None),
+            Ok(false) => (OperationStatus::Unverified, None),
+            Err(e) => (e.status(), Some(e.message)),
+        };
+        let verified = match get {
+            OperationStatus::Allowed => true,
+            OperationStatus::Unverified => list == OperationStatus::Allowed,
+            OperationStatus::Denied | OperationStatus::Failed => false,
+        };
+        Ok(Self {
+            list,
+            get,
+            probe_key,
+            list_error,
+            get_error,
+            verified,
+        })
+    }
+
+    pub fn summary(&self) -> String {
+        let describe = |status: OperationStatus, error: &Option<String>| match error {
+            Some(error) if status == OperationStatus::Failed => {
+                format!("{} ({})", status.as_str(), error)
+            }
+            _ => status.as_str().to_string(),
+        };
+        format!(
+            "List: {}, Get: {}",
+            describe(self.list, &self.list_error),
+            describe(self.get, &self.get_error)
+        )
+    }
+}
+
+impl OperationStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            OperationStatus::Allowed => "allowed",
+            OperationStatus::Denied => "denied",
+            OperationStatus::Unverified => "unverified",
+            OperationStatus::Failed => "failed",
+        }
+    }
+}
+
+/// Number of keys listed when looking for an object to probe Get with.
+const PROBE_LIST_KEYS: i32 = 20;
+
+/// Probes List with a short listing and Get with a one-byte ranged read of
+/// the catalog, or of the first listed non-empty object when there is no
+/// catalog. Both stay within `prefix`, so prefix-scoped tokens are checked
+/// correctly.
+pub async fn check_connection(
+    client: &R2Client,
+    bucket: &str,
+    prefix: Option<&str>,
+) -> Result<R2Permissions, String> {
+    let listed = match client.inject_faults().await {
+        Ok(()) => client
+            .client
+            .list_objects_v2()
+            .bucket(bucket)
+            .set_prefix(list_prefix(prefix))
+            .max_keys(PROBE_LIST_KEYS)
+            .send()
+            .await
+            .map_err(ProbeError::from_sdk),
+        Err(e) => Err(ProbeError::other(e)),
+    };
+    let first_key = listed
+        .as_ref()
+        .ok()
+        .and_then(|resp| probe_candidate(resp.contents()));
+
+    let mut probe_key = prefixed_key(prefix, crate::catalog::CATALOG_KEY);
+    let mut get = probe_get(client, bucket, &probe_key).await;
+    if let (Ok(false), Some(key)) = (&get, first_key) {
+        probe_key = key;
+        get = probe_get(client, bucket, &probe_key).await;
+    }
+
+    R2Permissions::from_probes(listed.map(|_| ()), get, probe_key)
+}
+
+/// The first listed object a ranged GET can read. Folder markers and other
+/// empty objects are skipped, since a range request on them fails with 416.
+fn probe_candidate(objects: &[Object]) -> Option<String> {
+    objects
+        .iter()
+        .filter(|obj| obj.size().unwrap_or_default() > 0)
+        .filter_map(|obj| obj.key())
+        .find(|key| !key.ends_with('/'))
+        .map(|key| key.to_string())
+}
+
+/// Reads the first byte of `key`. Returns `Ok(false)` if the key doesn't exist.
+/// An unsatisfiable range (the object is empty) still proves GET is allowed.
+async fn probe_get(client: &R2Client, bucket: &str, key: &str) -> Result<bool, ProbeError> {
+    client.inject_faults().await.map_err(ProbeError::other)?;
+    match client
+        .client
+        .get_object()
+        .bucket(bucket)
+        .key(key)
+        .range("bytes=0-0")
+        .send()
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(e) if e.as_service_error().is_some_and(|e| e.is_no_such_key()) => Ok(false),
+        Err(e) if e.raw_response().map(|r| r.status().as_u16()) == Some(416) => Ok(true),
+        Err(e) => Err(ProbeError::from_sdk(e)),
+    }
+}
+
+fn list_prefix(prefix: Option<&str>) -> Option<String> {
+    prefix
+        .filter(|p| !p.trim_matches('/').is_empty())
+        .map(|p| prefixed_key(Some(p), ""))
+}
+
+/// Lists the keys below `prefix`, relative to it.
+pub async fn list_objects(
+    client: &R2Client,
+    bucket: &str,
+    prefix: Option<&str>,
+) -> Result<Vec<String>, String> {
+    client.inject_faults().await?;
+    let list_prefix = list_prefix(prefix);
     let resp = client
+        .client
         .list_objects_v2()
         .bucket(bucket)
+        .set_prefix(list_prefix.clone())
         .send()
         .await
         .map_err(|e| format!("Failed to list objects: {}", e))?;
@@ -48,14 +308,20 @@ pub async fn list_objects(client: &Client, bucket: &str) -> Result<Vec<String>,
     let objects = resp
         .contents()
         .iter()
-        .filter_map(|obj| obj.key().map(|k| k.to_string()))
+        .filter_map(|obj| obj.key())
+        .map(|key| match &list_prefix {
+            Some(p) => key.strip_prefix(p.as_str()).unwrap_or(key).to_string(),
+            None => key.to_string(),
+        })
         .collect();
 
     Ok(objects)
 }
 
-pub async fn get_object(client: &Client, bucket: &str, key: &str) -> Result<Vec<u8>, String> {
+pub async fn get_object(client: &R2Client, bucket: &str, key: &str) -> Result<Vec<u8>, String> {
+    client.inject_faults().await?;
     let resp = client
+        .client
         .get_object()
         .bucket(bucket)
         .key(key)
@@ -71,3 +337,110 @@ pub async fn get_object(client: &Client, bucket: &str, key: &str) -> Result<Vec<
 
     Ok(data.into_bytes().to_vec())
 }
+
+pub async fn head_object(
+    client: &R2Client,
+    bucket: &str,
+    key: &str,
+) -> Result<ObjectMetadata, String> {
+    client.inject_faults().await?;
+    let resp = client
+        .client
+        .head_object()
+        .bucket(bucket)
+        .key(key)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to head object: {}", e))?;
+
+    Ok(ObjectMetadata {
+        size: resp.content_length().unwrap_or_default(),
+        etag: resp.e_tag().map(|etag| etag.to_string()),
+        last_modified: resp
+            .last_modified()
+            .and_then(|t| t.fmt(DateTimeFormat::DateTime).ok()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefixed_key() {
+        assert_eq!(prefixed_key(None, "catalog.json"), "catalog.json");
+        assert_eq!(prefixed_key(Some(""), "catalog.json"), "catalog.json");
+        assert_eq!(prefixed_key(Some("english"), "catalog.json"), "english/catalog.json");
+        assert_eq!(prefixed_key(Some("/english/"), "/catalog.json"), "english/catalog.json");
+        assert_eq!(list_prefix(Some("english")), Some("english/".to_string()));
+        assert_eq!(list_prefix(Some("/")), None);
+    }
+
+    #[test]
+    fn test_probe_candidate_skips_folder_markers_and_empty_objects() {
+        let object = |key: &str, size: i64| Object::builder().key(key).size(size).build();
+        let objects = [
+            object("books/", 0),
+            object("books/empty.txt", 0),
+            object("books/egu-int/cover.jpg", 1024),
+        ];
+        assert_eq!(
+            probe_candidate(&objects),
+            Some("books/egu-int/cover.jpg".to_string())
+        );
+        assert_eq!(probe_candidate(&objects[..2]), None);
+    }
+
+    fn denied() -> ProbeError {
+        ProbeError {
+            denied: true,
+            message: "AccessDenied".to_string(),
+        }
+    }
+
+    fn timeout() -> ProbeError {
+        ProbeError::other("timeout".to_string())
+    }
+
+    #[test]
+    fn test_permissions_from_probes() {
+        let read_only =
+            R2Permissions::from_probes(Err(denied()), Ok(true), "catalog.json".to_string())
+                .unwrap();
+        assert_eq!(read_only.list, OperationStatus::Denied);
+        assert_eq!(read_only.get, OperationStatus::Allowed);
+        assert!(read_only.verified);
+        assert_eq!(read_only.summary(), "List: denied, Get: allowed");
+
+        let list_only =
+            R2Permissions::from_probes(Ok(()), Err(denied()), "catalog.json".to_string())
+                .unwrap();
+        assert_eq!(list_only.get, OperationStatus::Denied);
+        assert!(!list_only.verified);
+
+        let empty = R2Permissions::from_probes(Ok(()), Ok(false), "catalog.json".to_string())
+            .unwrap();
+        assert_eq!(empty.get, OperationStatus::Unverified);
+        assert!(empty.verified);
+
+        let nothing_checked =
+            R2Permissions::from_probes(Err(denied()), Ok(false), "catalog.json".to_string())
+                .unwrap();
+        assert!(!nothing_checked.verified);
+
+        assert!(
+            R2Permissions::from_probes(Err(timeout()), Err(timeout()), "catalog.json".to_string())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_network_failures_are_not_reported_as_denied() {
+        let permissions =
+            R2Permissions::from_probes(Err(timeout()), Ok(true), "catalog.json".to_string())
+                .unwrap();
+        assert_eq!(permissions.list, OperationStatus::Failed);
+        assert_eq!(permissions.list_error, Some("timeout".to_string()));
+        assert_eq!(permissions.summary(), "List: failed (timeout), Get: allowed");
+    }
+}
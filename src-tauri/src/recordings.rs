@@ -0,0 +1,216 @@
+use chrono::Local;
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// Audio formats the webview's MediaRecorder can produce.
+const ALLOWED_FORMATS: [&str; 6] = ["webm", "ogg", "mp4", "m4a", "mp3", "wav"];
+
+const RECORDING_PREFIX: &str = "recording-";
+const MAX_NAME_ATTEMPTS: u32 = 100;
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Recording {
+    pub id: String,
+    pub product_code: String,
+    pub page_label: String,
+    pub path: String,
+    pub size: u64,
+}
+
+/// Accepts a value only if it is usable as a single path component.
+fn path_component<'a>(value: &'a str, what: &str) -> Result<&'a str, String> {
+    let mut components = Path::new(value).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(c)), None) if c == value => Ok(value),
+        _ => Err(format!("Invalid {}: {}", what, value)),
+    }
+}
+
+fn page_dir(root: &Path, product_code: &str, page_label: &str) -> Result<PathBuf, String> {
+    Ok(root
+        .join(path_component(product_code, "product code")?)
+        .join(path_component(page_label, "page label")?))
+}
+
+fn is_recording_id(id: &str) -> bool {
+    let path = Path::new(id);
+    id.starts_with(RECORDING_PREFIX)
+        && path.file_name().and_then(|n| n.to_str()) == Some(id)
+        && path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| ALLOWED_FORMATS.contains(&e))
+}
+
+pub fn save_recording(
+    root: &Path,
+    product_code: &str,
+    page_label: &str,
+    data: &[u8],
+    format: &str,
+) -> Result<Recording, String> {
+    let format = format.to_ascii_lowercase();
+    if !ALLOWED_FORMATS.contains(&format.as_str()) {
+        return Err(format!("Unsupported audio format: {}", format));
+    }
+    if data.is_empty() {
+        return Err("Recording is empty".to_string());
+    }
+
+    let dir = page_dir(root, product_code, page_label)?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let stem = format!(
+        "{}{}",
+        RECORDING_PREFIX,
+        Local::now().format("%Y%m%d-%H%M%S-%3f")
+    );
+    // Two saves can land in the same millisecond, so never overwrite. The
+    // suffix sorts after the plain name, keeping ids in save order.
+    let (id, path, mut file) = (0..MAX_NAME_ATTEMPTS)
+        .find_map(|attempt| {
+            let id = match attempt {
+                0 => format!("{}.{}", stem, format),
+                n => format!("{}_{:02}.{}", stem, n, format),
+            };
+            let path = dir.join(&id);
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => Some(Ok((id, path, file))),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => None,
+                Err(e) => Some(Err(e.to_string())),
+            }
+        })
+        .unwrap_or_else(|| Err("Could not pick a unique recording name".to_string()))?;
+    file.write_all(data).map_err(|e| e.to_string())?;
+
+    Ok(Recording {
+        id,
+        product_code: product_code.to_string(),
+        page_label: page_label.to_string(),
+        path: path.to_string_lossy().to_string(),
+        size: data.len() as u64,
+    })
+}
+
+pub fn list_recordings(
+    root: &Path,
+    product_code: &str,
+    page_label: &str,
+) -> Result<Vec<Recording>, String> {
+    let dir = page_dir(root, product_code, page_label)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut recordings = Vec::new();
+    for entry in fs::read_dir(&dir).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let id = entry.file_name().to_string_lossy().to_string();
+        if !is_recording_id(&id) {
+            continue;
+        }
+        recordings.push(Recording {
+            path: entry.path().to_string_lossy().to_string(),
+            size: entry.metadata().map(|m| m.len()).unwrap_or_default(),
+            product_code: product_code.to_string(),
+            page_label: page_label.to_string(),
+            id,
+        });
+    }
+    // Ids embed the timestamp, so this is oldest first
+    recordings.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(recordings)
+}
+
+fn recording_path(
+    root: &Path,
+    product_code: &str,
+    page_label: &str,
+    id: &str,
+) -> Result<PathBuf, String> {
+    if !is_recording_id(id) {
+        return Err(format!("Invalid recording id: {}", id));
+    }
+    Ok(page_dir(root, product_code, page_label)?.join(id))
+}
+
+/// Returns the audio bytes of a recording for playback in the webview.
+pub fn read_recording(
+    root: &Path,
+    product_code: &str,
+    page_label: &str,
+    id: &str,
+) -> Result<Vec<u8>, String> {
+    fs::read(recording_path(root, product_code, page_label, id)?).map_err(|e| e.to_string())
+}
+
+pub fn delete_recording(
+    root: &Path,
+    product_code: &str,
+    page_label: &str,
+    id: &str,
+) -> Result<(), String> {
+    fs::remove_file(recording_path(root, product_code, page_label, id)?).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_list_and_delete_recordings() {
+        let root = TempDir::new().unwrap();
+
+        let saved = save_recording(root.path(), "egu-int", "42", b"audio", "WEBM").unwrap();
+        assert!(saved.id.ends_with(".webm"));
+        assert_eq!(saved.size, 5);
+
+        let recordings = list_recordings(root.path(), "egu-int", "42").unwrap();
+        assert_eq!(recordings, vec![saved]);
+        assert!(list_recordings(root.path(), "egu-int", "43")
+            .unwrap()
+            .is_empty());
+
+        let id = recordings[0].id.clone();
+        assert_eq!(
+            read_recording(root.path(), "egu-int", "42", &id).unwrap(),
+            b"audio"
+        );
+        delete_recording(root.path(), "egu-int", "42", &id).unwrap();
+        assert!(list_recordings(root.path(), "egu-int", "42")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_saves_in_the_same_millisecond_do_not_overwrite() {
+        let root = TempDir::new().unwrap();
+        let ids: Vec<String> = (0..5)
+            .map(|i| {
+                save_recording(root.path(), "egu-int", "42", &[i + 1], "webm")
+                    .unwrap()
+                    .id
+            })
+            .collect();
+
+        let recordings = list_recordings(root.path(), "egu-int", "42").unwrap();
+        assert_eq!(recordings.len(), 5);
+        assert_eq!(
+            recordings.iter().map(|r| r.id.clone()).collect::<Vec<_>>(),
+            ids
+        );
+    }
+
+    #[test]
+    fn test_rejects_unsafe_input() {
+        let root = TempDir::new().unwrap();
+        assert!(save_recording(root.path(), "..", "42", b"audio", "webm").is_err());
+        assert!(save_recording(root.path(), "egu-int", "a/b", b"audio", "webm").is_err());
+        assert!(save_recording(root.path(), "egu-int", "42", b"audio", "exe").is_err());
+        assert!(save_recording(root.path(), "egu-int", "42", b"", "webm").is_err());
+        assert!(delete_recording(root.path(), "egu-int", "42", "../config.toml").is_err());
+        assert!(read_recording(root.path(), "egu-int", "42", "../config.toml").is_err());
+    }
+}
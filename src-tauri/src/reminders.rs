@@ -0,0 +1,160 @@
+use crate::config::{AppConfig, ReminderConfig};
+use chrono::{Datelike, Duration as ChronoDuration, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// How often the scheduler wakes up to check whether a reminder is due.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A scheduled reminder is only shown if the app is running within this
+/// many minutes of the configured time, so launching late doesn't nag.
+const FIRE_WINDOW_MINUTES: i64 = 5;
+
+#[derive(Default)]
+pub struct ReminderState {
+    inner: Mutex<ReminderTracker>,
+}
+
+#[derive(Default)]
+struct ReminderTracker {
+    last_fired: Option<NaiveDate>,
+    snoozed_until: Option<NaiveDateTime>,
+}
+
+impl ReminderState {
+    pub fn snooze(&self, until: NaiveDateTime) {
+        self.inner.lock().unwrap().snoozed_until = Some(until);
+    }
+
+    /// Returns whether a reminder should be shown at `now`, recording it as fired.
+    pub fn poll(&self, config: &ReminderConfig, now: NaiveDateTime) -> bool {
+        let mut tracker = self.inner.lock().unwrap();
+        if !config.enabled {
+            tracker.snoozed_until = None;
+            return false;
+        }
+
+        if let Some(until) = tracker.snoozed_until {
+            if now < until {
+                return false;
+            }
+            tracker.snoozed_until = None;
+            tracker.last_fired = Some(now.date());
+            return true;
+        }
+
+        if is_due(config, now, tracker.last_fired) {
+            tracker.last_fired = Some(now.date());
+            return true;
+        }
+        false
+    }
+}
+
+pub fn parse_time(time: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(time, "%H:%M")
+        .map_err(|e| format!("Invalid reminder time '{}': {}", time, e))
+}
+
+pub fn is_due(config: &ReminderConfig, now: NaiveDateTime, last_fired: Option<NaiveDate>) -> bool {
+    if !config.enabled || last_fired == Some(now.date()) {
+        return false;
+    }
+    if !config.days.contains(&now.weekday().number_from_monday()) {
+        return false;
+    }
+    let Ok(time) = parse_time(&config.time) else {
+        return false;
+    };
+
+    let scheduled = now.date().and_time(time);
+    now >= scheduled && now < scheduled + ChronoDuration::minutes(FIRE_WINDOW_MINUTES)
+}
+
+pub fn spawn_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+
+            let config = AppConfig::load_from_path(&crate::commands::get_config_path(&app))
+                .unwrap_or_default();
+            let now = Local::now().naive_local();
+            if app.state::<ReminderState>().poll(&config.system.reminders, now) {
+                show_reminder(&app, &config.system.language);
+            }
+        }
+    });
+}
+
+fn show_reminder(app: &AppHandle, language: &str) {
+    let (title, body) = match language {
+        "zh" => ("学习提醒", "该开始今天的英语学习了！"),
+        _ => ("Study reminder", "Time for today's English practice!"),
+    };
+    let _ = app.notification().builder().title(title).body(body).show();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: (i32, u32, u32), time: (u32, u32)) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(date.0, date.1, date.2)
+            .unwrap()
+            .and_hms_opt(time.0, time.1, 0)
+            .unwrap()
+    }
+
+    fn weekday_reminder() -> ReminderConfig {
+        ReminderConfig {
+            enabled: true,
+            time: "19:30".to_string(),
+            days: vec![1, 2, 3, 4, 5],
+        }
+    }
+
+    #[test]
+    fn test_is_due_within_window_on_selected_day() {
+        let config = weekday_reminder();
+        // 2026-10-12 is a Monday
+        assert!(!is_due(&config, at((2026, 10, 12), (19, 29)), None));
+        assert!(is_due(&config, at((2026, 10, 12), (19, 30)), None));
+        assert!(is_due(&config, at((2026, 10, 12), (19, 34)), None));
+        assert!(!is_due(&config, at((2026, 10, 12), (19, 35)), None));
+        // Saturday is not selected
+        assert!(!is_due(&config, at((2026, 10, 17), (19, 30)), None));
+    }
+
+    #[test]
+    fn test_is_due_fires_once_per_day() {
+        let config = weekday_reminder();
+        let now = at((2026, 10, 12), (19, 31));
+        assert!(!is_due(&config, now, Some(now.date())));
+    }
+
+    #[test]
+    fn test_disabled_or_invalid_time_never_due() {
+        let mut config = weekday_reminder();
+        config.enabled = false;
+        assert!(!is_due(&config, at((2026, 10, 12), (19, 30)), None));
+
+        config.enabled = true;
+        config.time = "7pm".to_string();
+        assert!(!is_due(&config, at((2026, 10, 12), (19, 30)), None));
+    }
+
+    #[test]
+    fn test_snooze_refires_after_delay() {
+        let config = weekday_reminder();
+        let state = ReminderState::default();
+        assert!(state.poll(&config, at((2026, 10, 12), (19, 30))));
+        assert!(!state.poll(&config, at((2026, 10, 12), (19, 31))));
+
+        state.snooze(at((2026, 10, 12), (19, 40)));
+        assert!(!state.poll(&config, at((2026, 10, 12), (19, 39))));
+        assert!(state.poll(&config, at((2026, 10, 12), (19, 40))));
+        assert!(!state.poll(&config, at((2026, 10, 12), (19, 41))));
+    }
+}
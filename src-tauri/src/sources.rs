@@ -0,0 +1,197 @@
+use crate::config::{AppConfig, BookSource, FaultInjection, TimeoutConfig};
+use serde::Serialize;
+use std::path::{Component, Path, PathBuf};
+
+/// An object read from one of the configured book sources.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ResolvedObject {
+    /// Index of the serving source in priority order (0 is the primary source).
+    pub source_index: usize,
+    pub source_type: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct SourceHealth {
+    pub source_index: usize,
+    pub source_type: String,
+    pub ok: bool,
+    pub message: String,
+}
+
+pub fn source_type(source: &BookSource) -> String {
+    match source {
+        BookSource::Local { .. } => "Local".to_string(),
+        BookSource::CloudflareR2 { .. } => "CloudflareR2".to_string(),
+    }
+}
+
+fn local_object_path(root: &str, key: &str) -> Result<PathBuf, String> {
+    let key_path = Path::new(key);
+    if key_path
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return Err(format!("Invalid object key: {}", key));
+    }
+    Ok(Path::new(root).join(key_path))
+}
+
+async fn read_from_source(
+    source: &BookSource,
+    key: &str,
+    timeouts: &TimeoutConfig,
+    faults: &FaultInjection,
+) -> Result<Vec<u8>, String> {
+    match source {
+        BookSource::Local { path } => {
+            let path = local_object_path(path, key)?;
+            // The placeholder check stats the file (and reads attributes on
+            // Windows), so keep it off the async workers like the read itself
+            let probe = path.clone();
+            let placeholder =
+                tokio::task::spawn_blocking(move || crate::cloud_folders::is_placeholder(&probe))
+                    .await
+                    .map_err(|e| e.to_string())?;
+            if placeholder {
+                crate::cloud_folders::hydrate(&path, timeouts.hydration()).await?;
+            }
+            tokio::fs::read(&path)
+                .await
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+        }
+        BookSource::CloudflareR2 {
+            bucket_name,
+            key_prefix,
+            ..
+        } => {
+            let client = crate::r2::create_r2_client(source, timeouts.r2(), faults).await?;
+            let key = crate::r2::prefixed_key(key_prefix.as_deref(), key);
+            crate::r2::get_object(&client, bucket_name, &key).await
+        }
+    }
+}
+
+/// Reads `key` from the first configured book source that can serve it,
+/// trying sources in priority order.
+pub async fn read_object(config: &AppConfig, key: &str) -> Result<ResolvedObject, String> {
+    let sources = config.book_sources();
+    if sources.is_empty() {
+        return Err("No book source configured".to_string());
+    }
+
+    let mut errors = Vec::new();
+    for (index, source) in sources.iter().enumerate() {
+        match read_from_source(source, key, &config.timeouts, &config.fault_injection).await {
+            Ok(data) => {
+                return Ok(ResolvedObject {
+                    source_index: index,
+                    source_type: source_type(source),
+                    data,
+                })
+            }
+            Err(e) => errors.push(format!("[{}] {}: {}", index, source_type(source), e)),
+        }
+    }
+
+    Err(format!(
+        "No source could serve {}: {}",
+        key,
+        errors.join("; ")
+    ))
+}
+
+pub async fn check_source(index: usize, source: &BookSource, config: &AppConfig) -> SourceHealth {
+    let result = match source {
+        BookSource::Local { path } => {
+            if Path::new(path).is_dir() {
+                match crate::cloud_folders::detect_provider(Path::new(path)) {
+                    Some(provider) => Ok(format!("Folder is accessible ({})", provider.label())),
+                    None => Ok("Folder is accessible".to_string()),
+                }
+            } else {
+                Err(format!("Folder not found: {}", path))
+            }
+        }
+        BookSource::CloudflareR2 {
+            bucket_name,
+            key_prefix,
+            ..
+        } => {
+            let client =
+                crate::r2::create_r2_client(source, config.timeouts.r2(), &config.fault_injection)
+                    .await;
+            match client {
+                Ok(client) => {
+                    crate::r2::check_connection(&client, bucket_name, key_prefix.as_deref())
+                        .await
+                        .and_then(|permissions| {
+                            if permissions.verified {
+                                Ok(permissions.summary())
+                            } else {
+                                Err(permissions.summary())
+                            }
+                        })
+                }
+                Err(e) => Err(e),
+            }
+        }
+    };
+
+    let (ok, message) = match result {
+        Ok(message) => (true, message),
+        Err(message) => (false, message),
+    };
+    SourceHealth {
+        source_index: index,
+        source_type: source_type(source),
+        ok,
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn local(dir: &TempDir) -> BookSource {
+        BookSource::Local {
+            path: dir.path().to_string_lossy().to_string(),
+        }
+    }
+
+    fn config_with(sources: Vec<BookSource>) -> AppConfig {
+        let mut sources = sources.into_iter();
+        let mut config = AppConfig::new();
+        config.book_source = sources.next();
+        config.fallback_sources = sources.collect();
+        config
+    }
+
+    #[tokio::test]
+    async fn test_read_object_falls_back_in_order() {
+        let primary = TempDir::new().unwrap();
+        let fallback = TempDir::new().unwrap();
+        fs::create_dir_all(fallback.path().join("books")).unwrap();
+        fs::write(fallback.path().join("books/cover.jpg"), b"cover").unwrap();
+
+        let config = config_with(vec![local(&primary), local(&fallback)]);
+        let resolved = read_object(&config, "books/cover.jpg")
+            .await
+            .expect("Fallback source should serve the object");
+
+        assert_eq!(resolved.source_index, 1);
+        assert_eq!(resolved.source_type, "Local");
+        assert_eq!(resolved.data, b"cover");
+    }
+
+    #[tokio::test]
+    async fn test_read_object_rejects_parent_keys() {
+        let dir = TempDir::new().unwrap();
+        let config = config_with(vec![local(&dir)]);
+        let result = read_object(&config, "../config.toml").await;
+        assert!(result.is_err());
+    }
+}
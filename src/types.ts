@@ -16,6 +16,7 @@ export interface CloudflareR2BookSource {
     access_key_id: string;
     secret_access_key: string;
     public_url?: string;
+    key_prefix?: string;
   };
 }
 
@@ -35,13 +36,56 @@ export interface PostgreSQLDatabase {
 
 export type DatabaseConnection = PostgreSQLDatabase;
 
+export interface ReminderConfig {
+  enabled: boolean;
+  time: string;
+  days: number[];
+}
+
 export interface SystemConfig {
   language: string;
   theme: 'system' | 'light' | 'dark';
+  reminders?: ReminderConfig;
+  crash_reporting?: boolean;
+}
+
+export interface TimeoutConfig {
+  r2_secs: number;
+  database_secs: number;
+  hydration_secs: number;
+}
+
+export interface FaultInjection {
+  failure_percent: number;
+  latency_ms: number;
 }
 
 export interface AppConfig {
   system: SystemConfig;
   book_source: BookSource | null;
+  fallback_sources?: BookSource[];
   database: DatabaseConnection | null;
+  timeouts?: TimeoutConfig;
+  fault_injection?: FaultInjection;
+}
+
+export interface TaskProgress {
+  task_id: string;
+  kind: 'migration' | 'source_check';
+  phase: 'started' | 'running' | 'finished' | 'failed';
+  percent: number | null;
+  message: string | null;
+  eta_secs: number | null;
+  rate_per_sec: number | null;
+}
+
+export type OperationStatus = 'allowed' | 'denied' | 'unverified' | 'failed';
+
+export interface R2Permissions {
+  list: OperationStatus;
+  get: OperationStatus;
+  probe_key: string;
+  list_error: string | null;
+  get_error: string | null;
+  verified: boolean;
 }